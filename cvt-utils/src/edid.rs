@@ -0,0 +1,209 @@
+//! Parsing of the bits of a monitor's E-EDID we care about: the declared
+//! pixel clock and sync frequency ceilings, and which blanking standards
+//! (GTF/CVT) the panel claims to support.
+//!
+//! Only the base 128-byte EDID block's display range limits descriptor is
+//! parsed; CEA/DisplayID extension blocks are ignored.
+
+use std::fmt;
+
+/// The EDID header every base block starts with (VESA EDID spec, section 3.1).
+const HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+
+/// Offsets of the four 18-byte descriptor blocks in the base EDID block.
+const DESCRIPTOR_OFFSETS: [usize; 4] = [54, 72, 90, 108];
+
+/// Descriptor tag for the Display Range Limits descriptor.
+const RANGE_LIMITS_TAG: u8 = 0xFD;
+
+/// The fields of a monitor's EDID relevant to validating a generated timing
+/// against the panel's advertised limits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edid {
+    /// Maximum pixel clock the monitor accepts (MHz).
+    pub max_pixel_clock_mhz: f64,
+    /// Minimum vertical field rate (Hz).
+    pub min_v_field_rate_hz: u32,
+    /// Maximum vertical field rate (Hz).
+    pub max_v_field_rate_hz: u32,
+    /// Minimum horizontal line rate (KHz).
+    pub min_h_line_rate_khz: u32,
+    /// Maximum horizontal line rate (KHz).
+    pub max_h_line_rate_khz: u32,
+    /// Whether the monitor supports default GTF timing formulas.
+    pub supports_gtf: bool,
+    /// Whether the monitor supports the GTF secondary curve.
+    pub supports_secondary_gtf: bool,
+    /// Whether the monitor supports CVT timing formulas (EDID 1.4).
+    pub supports_cvt: bool,
+}
+
+/// An error encountered while parsing an EDID blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdidError {
+    /// The blob is shorter than the 128-byte base EDID block.
+    TooShort,
+    /// The blob doesn't start with the fixed EDID header.
+    InvalidHeader,
+    /// The base block's checksum byte doesn't make all 128 bytes sum to 0.
+    ChecksumMismatch,
+    /// None of the four descriptor blocks is a Display Range Limits descriptor.
+    NoRangeLimitsDescriptor,
+}
+
+impl fmt::Display for EdidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EdidError::TooShort => write!(f, "EDID blob is shorter than 128 bytes"),
+            EdidError::InvalidHeader => write!(f, "EDID blob has an invalid header"),
+            EdidError::ChecksumMismatch => write!(f, "EDID base block checksum mismatch"),
+            EdidError::NoRangeLimitsDescriptor => {
+                write!(f, "EDID has no Display Range Limits descriptor")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EdidError {}
+
+impl Edid {
+    /// Parses the base 128-byte EDID block out of `data`.
+    ///
+    /// `data` may be longer (e.g. when extension blocks are present); only
+    /// the first 128 bytes are consulted.
+    pub fn parse(data: &[u8]) -> Result<Self, EdidError> {
+        if data.len() < 128 {
+            return Err(EdidError::TooShort);
+        }
+
+        let base = &data[0..128];
+        if base[0..8] != HEADER {
+            return Err(EdidError::InvalidHeader);
+        }
+
+        let checksum = base.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        if checksum != 0 {
+            return Err(EdidError::ChecksumMismatch);
+        }
+
+        for &offset in &DESCRIPTOR_OFFSETS {
+            let desc = &base[offset..offset + 18];
+            // A detailed timing descriptor has a nonzero pixel clock in the
+            // first two bytes; the other descriptor types start with 0x0000
+            // followed by a flag byte and a tag byte identifying the type.
+            if desc[0] == 0x00 && desc[1] == 0x00 && desc[2] == 0x00 && desc[3] == RANGE_LIMITS_TAG
+            {
+                return Ok(Self::parse_range_limits(desc));
+            }
+        }
+
+        Err(EdidError::NoRangeLimitsDescriptor)
+    }
+
+    fn parse_range_limits(desc: &[u8]) -> Self {
+        let offset_flags = desc[4];
+        let v_min_offset = if offset_flags & 0x01 != 0 { 255 } else { 0 };
+        let v_max_offset = if offset_flags & 0x02 != 0 { 255 } else { 0 };
+        let h_min_offset = if offset_flags & 0x04 != 0 { 255 } else { 0 };
+        let h_max_offset = if offset_flags & 0x08 != 0 { 255 } else { 0 };
+
+        let min_v_field_rate_hz = desc[5] as u32 + v_min_offset;
+        let max_v_field_rate_hz = desc[6] as u32 + v_max_offset;
+        let min_h_line_rate_khz = desc[7] as u32 + h_min_offset;
+        let max_h_line_rate_khz = desc[8] as u32 + h_max_offset;
+        let max_pixel_clock_mhz = desc[9] as f64 * 10.0;
+
+        // EDID 1.4 timing support flag (byte 10 of the descriptor).
+        let timing_support = desc[10];
+
+        Self {
+            max_pixel_clock_mhz,
+            min_v_field_rate_hz,
+            max_v_field_rate_hz,
+            min_h_line_rate_khz,
+            max_h_line_rate_khz,
+            supports_gtf: timing_support == 0x00,
+            supports_secondary_gtf: timing_support == 0x02,
+            supports_cvt: timing_support & 0x04 != 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a minimal valid 128-byte base EDID block with a Display Range
+    /// Limits descriptor at offset 54, and a checksum that makes the full
+    /// block sum to 0.
+    fn sample_edid(offset_flags: u8, range_limits: [u8; 5], timing_support: u8) -> [u8; 128] {
+        let mut data = [0u8; 128];
+        data[0..8].copy_from_slice(&HEADER);
+
+        let desc = &mut data[54..72];
+        desc[3] = RANGE_LIMITS_TAG;
+        desc[4] = offset_flags;
+        desc[5..10].copy_from_slice(&range_limits);
+        desc[10] = timing_support;
+
+        let sum = data[..127].iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        data[127] = 0u8.wrapping_sub(sum);
+        data
+    }
+
+    #[test]
+    fn parses_a_valid_range_limits_descriptor() {
+        let data = sample_edid(0x00, [50, 75, 30, 80, 20], 0x04);
+
+        let edid = Edid::parse(&data).unwrap();
+        assert_eq!(edid.min_v_field_rate_hz, 50);
+        assert_eq!(edid.max_v_field_rate_hz, 75);
+        assert_eq!(edid.min_h_line_rate_khz, 30);
+        assert_eq!(edid.max_h_line_rate_khz, 80);
+        assert_eq!(edid.max_pixel_clock_mhz, 200.0);
+        assert!(edid.supports_cvt);
+        assert!(!edid.supports_gtf);
+        assert!(!edid.supports_secondary_gtf);
+    }
+
+    #[test]
+    fn applies_the_255_hz_khz_offset_flags() {
+        let data = sample_edid(0x0F, [50, 75, 30, 80, 20], 0x00);
+
+        let edid = Edid::parse(&data).unwrap();
+        assert_eq!(edid.min_v_field_rate_hz, 50 + 255);
+        assert_eq!(edid.max_v_field_rate_hz, 75 + 255);
+        assert_eq!(edid.min_h_line_rate_khz, 30 + 255);
+        assert_eq!(edid.max_h_line_rate_khz, 80 + 255);
+        assert!(edid.supports_gtf);
+    }
+
+    #[test]
+    fn rejects_blobs_shorter_than_128_bytes() {
+        assert_eq!(Edid::parse(&[0u8; 64]), Err(EdidError::TooShort));
+    }
+
+    #[test]
+    fn rejects_an_invalid_header() {
+        let mut data = sample_edid(0x00, [50, 75, 30, 80, 20], 0x04);
+        data[0] = 0xAB;
+        assert_eq!(Edid::parse(&data), Err(EdidError::InvalidHeader));
+    }
+
+    #[test]
+    fn rejects_a_bad_checksum() {
+        let mut data = sample_edid(0x00, [50, 75, 30, 80, 20], 0x04);
+        data[127] ^= 0xFF;
+        assert_eq!(Edid::parse(&data), Err(EdidError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn rejects_a_missing_range_limits_descriptor() {
+        let mut data = [0u8; 128];
+        data[0..8].copy_from_slice(&HEADER);
+        let sum = data[..127].iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        data[127] = 0u8.wrapping_sub(sum);
+
+        assert_eq!(Edid::parse(&data), Err(EdidError::NoRangeLimitsDescriptor));
+    }
+}
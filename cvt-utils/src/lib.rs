@@ -1,3 +1,8 @@
+pub mod edid;
+
+use std::fmt;
+
+use edid::Edid;
 
 /// Represents CVT timings.
 /// To better understand CVT timings, read the README of this crate
@@ -87,7 +92,6 @@ enum AspectRatio {
 
 impl CvtTimings {
     // generates  video timings according to the VESA CVT standard
-    // look into GTF for the future
     // https://glenwing.github.io/docs/VESA-GTF-1.1.pdf
 
     /// Generates CVT timings according to the input given.
@@ -350,6 +354,500 @@ impl CvtTimings {
             if self.interlaced { "Interlace" } else { "" }
         )
     }
+
+    /// Generates an `fbset`/`fb.modes` timing block for this timing, for use
+    /// on framebuffer/KMS systems that don't go through an X11 driver.
+    pub fn generate_fb_modeline(&self) -> String {
+        let pixclock_ps = (1_000_000_000_000.0 / self.pixel_clock).round() as u64;
+
+        format!(
+            "mode \"{}x{}_{:.2}{}\"\n    geometry {} {} {} {} 24\n    timings {} {} {} {} {} {} {}\n    hsync {}\n    vsync {}\n    vmode {}\nendmode",
+            self.h_active,
+            self.v_active,
+            self.v_freq,
+            if self.interlaced { "i" } else { "" },
+            self.h_active,
+            self.v_active,
+            self.h_active,
+            self.v_active,
+            pixclock_ps,
+            self.h_back_porch,
+            self.h_front_porch,
+            self.v_back_porch,
+            self.v_front_porch,
+            self.h_sync,
+            self.v_sync,
+            if self.h_sync_polarity { "high" } else { "low" },
+            if self.v_sync_polarity { "high" } else { "low" },
+            if self.interlaced { "interlaced" } else { "nonlaced" },
+        )
+    }
+
+    /// Generates a kernel `drmModeModeInfo`-style field listing for this
+    /// timing, for use with the DRM/KMS mode-setting APIs (or tools like
+    /// `modetest`/`v4l2-ctl` that print modes in the same shape).
+    pub fn generate_drm_modeinfo(&self) -> String {
+        let mut flags = vec![if self.h_sync_polarity {
+            "DRM_MODE_FLAG_PHSYNC"
+        } else {
+            "DRM_MODE_FLAG_NHSYNC"
+        }];
+        flags.push(if self.v_sync_polarity {
+            "DRM_MODE_FLAG_PVSYNC"
+        } else {
+            "DRM_MODE_FLAG_NVSYNC"
+        });
+        if self.interlaced {
+            flags.push("DRM_MODE_FLAG_INTERLACE");
+        }
+
+        format!(
+            "drmModeModeInfo {{ clock: {}, hdisplay: {}, hsync_start: {}, hsync_end: {}, htotal: {}, vdisplay: {}, vsync_start: {}, vsync_end: {}, vtotal: {}, flags: {} }}",
+            (self.pixel_clock / 1000.0).round() as u32,
+            self.h_active,
+            self.h_active + self.h_front_porch,
+            self.h_active + self.h_front_porch + self.h_sync,
+            self.h_total,
+            self.v_active,
+            self.v_active + self.v_front_porch,
+            self.v_active + self.v_front_porch + self.v_sync,
+            self.v_total,
+            flags.join(" | "),
+        )
+    }
+
+    /// Checks `self` against the limits a monitor's EDID advertises,
+    /// rejecting timings that exceed its maximum pixel clock or sync
+    /// frequency ranges.
+    ///
+    /// This only checks the envelope the monitor *declares*; it doesn't
+    /// guarantee the monitor will actually accept the mode.
+    pub fn validate_against(&self, edid: &Edid) -> Result<(), TimingError> {
+        let pixel_clock_mhz = self.pixel_clock / 1_000_000.0;
+        if pixel_clock_mhz > edid.max_pixel_clock_mhz {
+            return Err(TimingError::PixelClockExceedsMax {
+                requested: pixel_clock_mhz,
+                max: edid.max_pixel_clock_mhz,
+            });
+        }
+
+        let h_freq_khz = self.h_freq / 1000.0;
+        if h_freq_khz > edid.max_h_line_rate_khz as f64 {
+            return Err(TimingError::HFreqExceedsMax {
+                requested: h_freq_khz,
+                max: edid.max_h_line_rate_khz as f64,
+            });
+        }
+
+        if self.v_freq > edid.max_v_field_rate_hz as f64 {
+            return Err(TimingError::VFreqExceedsMax {
+                requested: self.v_freq,
+                max: edid.max_v_field_rate_hz as f64,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// An error returned by [`CvtTimings::validate_against`] when a generated
+/// timing falls outside the envelope a monitor's EDID declares.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimingError {
+    /// The timing's pixel clock (MHz) exceeds the monitor's declared maximum.
+    PixelClockExceedsMax { requested: f64, max: f64 },
+    /// The timing's horizontal scan frequency (KHz) exceeds the monitor's
+    /// declared maximum.
+    HFreqExceedsMax { requested: f64, max: f64 },
+    /// The timing's vertical scan frequency (Hz) exceeds the monitor's
+    /// declared maximum.
+    VFreqExceedsMax { requested: f64, max: f64 },
+}
+
+impl fmt::Display for TimingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimingError::PixelClockExceedsMax { requested, max } => write!(
+                f,
+                "pixel clock {:.2} MHz exceeds monitor maximum of {:.2} MHz",
+                requested, max
+            ),
+            TimingError::HFreqExceedsMax { requested, max } => write!(
+                f,
+                "horizontal frequency {:.2} KHz exceeds monitor maximum of {:.2} KHz",
+                requested, max
+            ),
+            TimingError::VFreqExceedsMax { requested, max } => write!(
+                f,
+                "vertical frequency {:.2} Hz exceeds monitor maximum of {:.2} Hz",
+                requested, max
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TimingError {}
+
+impl CvtTimings {
+    /// Parses the inverse of [`CvtTimings::generate_modeline`]: an X11
+    /// `Modeline "name" clock hdisp hsyncstart hsyncend htotal vdisp
+    /// vsyncstart vsyncend vtotal [+/-HSync] [+/-VSync] [Interlace]` line.
+    ///
+    /// This lets a hand-tuned or captured modeline be diffed against a
+    /// freshly generated one, or re-derived to check whether it's CVT-,
+    /// CVT-RB-, or GTF-shaped.
+    pub fn parse_modeline(s: &str) -> Result<Self, ParseError> {
+        let mut tokens = s.split_whitespace();
+
+        match tokens.next() {
+            Some(kw) if kw.eq_ignore_ascii_case("modeline") => {}
+            Some(other) => return Err(ParseError::UnexpectedKeyword(other.to_string())),
+            None => return Err(ParseError::Empty),
+        }
+
+        tokens.next().ok_or(ParseError::MissingName)?;
+
+        let fields: Vec<&str> = tokens.collect();
+        if fields.len() < 9 {
+            return Err(ParseError::TooFewFields {
+                expected: 9,
+                found: fields.len(),
+            });
+        }
+
+        let parse_f64 = |s: &str| {
+            s.parse::<f64>()
+                .map_err(|_| ParseError::InvalidNumber(s.to_string()))
+        };
+        let parse_u32 = |s: &str| {
+            s.parse::<u32>()
+                .map_err(|_| ParseError::InvalidNumber(s.to_string()))
+        };
+
+        let clock_mhz = parse_f64(fields[0])?;
+        let h_active = parse_u32(fields[1])?;
+        let h_sync_start = parse_u32(fields[2])?;
+        let h_sync_end = parse_u32(fields[3])?;
+        let h_total = parse_u32(fields[4])?;
+        let v_active = parse_u32(fields[5])?;
+        let v_sync_start = parse_u32(fields[6])?;
+        let v_sync_end = parse_u32(fields[7])?;
+        let v_total = parse_u32(fields[8])?;
+
+        let mut h_sync_polarity = false;
+        let mut v_sync_polarity = true;
+        let mut interlaced = false;
+        for flag in &fields[9..] {
+            match flag.to_ascii_lowercase().as_str() {
+                "+hsync" => h_sync_polarity = true,
+                "-hsync" => h_sync_polarity = false,
+                "+vsync" => v_sync_polarity = true,
+                "-vsync" => v_sync_polarity = false,
+                "interlace" => interlaced = true,
+                other => return Err(ParseError::UnknownFlag(other.to_string())),
+            }
+        }
+
+        let h_blank = h_total
+            .checked_sub(h_active)
+            .ok_or(ParseError::InvalidOrdering)?;
+        let h_front_porch = h_sync_start
+            .checked_sub(h_active)
+            .ok_or(ParseError::InvalidOrdering)?;
+        let h_sync = h_sync_end
+            .checked_sub(h_sync_start)
+            .ok_or(ParseError::InvalidOrdering)?;
+        let h_back_porch = h_total
+            .checked_sub(h_sync_end)
+            .ok_or(ParseError::InvalidOrdering)?;
+        let v_blank = v_total
+            .checked_sub(v_active)
+            .ok_or(ParseError::InvalidOrdering)?;
+        let v_front_porch = v_sync_start
+            .checked_sub(v_active)
+            .ok_or(ParseError::InvalidOrdering)?;
+        let v_sync = v_sync_end
+            .checked_sub(v_sync_start)
+            .ok_or(ParseError::InvalidOrdering)?;
+        let v_back_porch = v_total
+            .checked_sub(v_sync_end)
+            .ok_or(ParseError::InvalidOrdering)?;
+
+        let pixel_clock = clock_mhz * 1_000_000.0;
+        let h_freq = pixel_clock / h_total as f64;
+        let v_freq = pixel_clock / (h_total as f64 * v_total as f64);
+
+        Ok(Self {
+            pixel_clock,
+            h_total,
+            h_active,
+            h_blank,
+            h_front_porch,
+            h_sync,
+            h_back_porch,
+            h_sync_polarity,
+            h_freq: (h_freq * 100.0).round() / 100.0,
+            h_period: 1.0 / h_freq,
+            v_total,
+            v_active,
+            v_blank,
+            v_front_porch,
+            v_sync,
+            v_back_porch,
+            v_sync_polarity,
+            v_freq: (v_freq * 100.0).round() / 100.0,
+            v_period: 1.0 / v_freq,
+            interlaced,
+        })
+    }
+}
+
+/// An error returned by [`CvtTimings::parse_modeline`] when a string doesn't
+/// match the expected `Modeline` syntax.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input was empty.
+    Empty,
+    /// The first token wasn't the `Modeline` keyword.
+    UnexpectedKeyword(String),
+    /// The `"name"` field was missing.
+    MissingName,
+    /// Fewer numeric fields were present than the format requires.
+    TooFewFields { expected: usize, found: usize },
+    /// A numeric field couldn't be parsed.
+    InvalidNumber(String),
+    /// A trailing flag wasn't one of `+/-HSync`, `+/-VSync`, or `Interlace`.
+    UnknownFlag(String),
+    /// The hsync/vsync/total fields aren't in the required
+    /// active <= sync_start <= sync_end <= total ordering.
+    InvalidOrdering,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty modeline string"),
+            ParseError::UnexpectedKeyword(kw) => {
+                write!(f, "expected \"Modeline\", found \"{}\"", kw)
+            }
+            ParseError::MissingName => write!(f, "missing mode name"),
+            ParseError::TooFewFields { expected, found } => write!(
+                f,
+                "expected at least {} numeric fields, found {}",
+                expected, found
+            ),
+            ParseError::InvalidNumber(s) => write!(f, "invalid number: \"{}\"", s),
+            ParseError::UnknownFlag(flag) => write!(f, "unknown modeline flag: \"{}\"", flag),
+            ParseError::InvalidOrdering => write!(
+                f,
+                "hsync/vsync/total fields are not in active <= sync_start <= sync_end <= total order"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Generator for video timings according to the older VESA GTF standard.
+///
+/// GTF predates CVT and is still what some older monitors and projectors
+/// advertise support for in their EDID instead of CVT. Unlike CVT, GTF
+/// derives horizontal blanking from a "blanking duty cycle" curve instead
+/// of using fixed reduced-blanking constants.
+///
+/// https://glenwing.github.io/docs/VESA-GTF-1.1.pdf
+pub struct GtfTimings;
+
+impl GtfTimings {
+    /// Generates GTF timings according to the input given.
+    ///
+    /// `secondary` selects the GTF secondary curve (C=30, M=300, K=128, J=9)
+    /// instead of the default primary curve (C=40, M=600, K=128, J=20).
+    ///
+    /// Returns a [`CvtTimings`] so the result can be fed straight into
+    /// [`CvtTimings::generate_modeline`] like any other generated timing.
+    pub fn generate(
+        h_pixels: u32,
+        v_lines: u32,
+        refresh_rate: f64,
+        interlaced: bool,
+        margins: bool,
+        secondary: bool,
+    ) -> CvtTimings {
+        Self::generate_with_curve(
+            h_pixels,
+            v_lines,
+            refresh_rate,
+            interlaced,
+            margins,
+            if secondary {
+                (30.0, 300.0, 128.0, 9.0)
+            } else {
+                (40.0, 600.0, 128.0, 20.0)
+            },
+        )
+    }
+
+    /// Same as [`GtfTimings::generate`], but lets the caller override the
+    /// C/M/K/J blanking duty cycle curve parameters directly instead of
+    /// picking between the primary and secondary curves.
+    pub fn generate_with_curve(
+        h_pixels: u32,
+        v_lines: u32,
+        refresh_rate: f64,
+        interlaced: bool,
+        margins: bool,
+        (c, m, k, j): (f64, f64, f64, f64),
+    ) -> CvtTimings {
+        let cell_gran: f64 = 8.0;
+        let margin_per: f64 = 1.8;
+        let gtf_min_porch: f64 = 1.0;
+        let gtf_v_sync_rqd: f64 = 3.0;
+        let gtf_h_sync_perc: f64 = 8.0;
+        let gtf_min_vsync_bp: f64 = 550.0;
+        let clock_step: f64 = 0.25;
+
+        let c_prime = ((c - j) * k / 256.0) + j;
+        let m_prime = (k / 256.0) * m;
+
+        // 5.2 Computation of Common Parameters (shared with CVT)
+        let v_field_rate_rqd = if interlaced {
+            refresh_rate * 2.0
+        } else {
+            refresh_rate
+        };
+
+        let h_pixels_rnd = (h_pixels as f64 / cell_gran).floor() * cell_gran;
+
+        let left_margin = if margins {
+            ((h_pixels_rnd * margin_per / 100.0) / cell_gran).floor() * cell_gran
+        } else {
+            0.0
+        };
+        let right_margin = left_margin;
+
+        let total_active_pixels = h_pixels_rnd + left_margin + right_margin;
+
+        let v_lines_rnd = if interlaced {
+            ((v_lines as f64) / 2.0).floor()
+        } else {
+            (v_lines as f64).floor()
+        };
+
+        let top_margin = if margins {
+            (v_lines_rnd * margin_per / 100.0).floor()
+        } else {
+            0.0
+        };
+        let bot_margin = top_margin;
+
+        let interlace = if interlaced { 0.5 } else { 0.0 };
+
+        let h_period_est = ((1.0 / v_field_rate_rqd) - gtf_min_vsync_bp / 1000000.0)
+            / (v_lines_rnd + 2.0 * top_margin + gtf_min_porch + interlace)
+            * 1000000.0;
+
+        let v_sync_bp = (gtf_min_vsync_bp / h_period_est).floor() + 1.0;
+
+        let v_blank = v_sync_bp + gtf_min_porch;
+        let v_front_porch = gtf_min_porch;
+        let v_back_porch = v_sync_bp - gtf_v_sync_rqd;
+        let total_v_lines =
+            v_lines_rnd + top_margin + bot_margin + v_sync_bp + interlace + gtf_min_porch;
+
+        let ideal_duty_cycle = c_prime - (m_prime * h_period_est / 1000.0);
+        let h_blank = (total_active_pixels * ideal_duty_cycle
+            / (100.0 - ideal_duty_cycle)
+            / (2.0 * cell_gran))
+            .round()
+            * (2.0 * cell_gran);
+        let total_pixels = total_active_pixels + h_blank;
+
+        let h_sync = (gtf_h_sync_perc / 100.0 * total_pixels / cell_gran).round() * cell_gran;
+        let h_back_porch = h_blank / 2.0;
+        let h_front_porch = h_blank - h_sync - h_back_porch;
+
+        let act_pix_freq = clock_step * (total_pixels / h_period_est / clock_step).floor();
+
+        let pclock = act_pix_freq * 1000000.0;
+        let h_freq = pclock / total_pixels;
+        let v_freq = pclock / (total_v_lines * total_pixels);
+
+        CvtTimings {
+            pixel_clock: pclock,
+            h_active: total_active_pixels as u32,
+            h_blank: h_blank as u32,
+            h_total: total_pixels as u32,
+            v_active: v_lines_rnd as u32,
+            v_blank: v_blank as u32,
+            v_total: total_v_lines as u32,
+            h_freq: (h_freq * 100.0).round() / 100.0,
+            v_freq: (v_freq * 100.0).round() / 100.0,
+            h_period: 1.0 / h_freq,
+            v_period: 1.0 / v_freq,
+            h_front_porch: h_front_porch as u32,
+            h_sync: h_sync as u32,
+            h_back_porch: h_back_porch as u32,
+            h_sync_polarity: false,
+            v_front_porch: v_front_porch as u32,
+            v_sync: gtf_v_sync_rqd as u32,
+            v_back_porch: v_back_porch as u32,
+            v_sync_polarity: true,
+            interlaced,
+        }
+    }
+}
+
+impl CvtTimings {
+    /// Reproduces the VESA CVT conformance checks against `self`: whether
+    /// the aspect ratio is one of the spec-defined ratios (4:3, 16:9,
+    /// 16:10, 5:4, 15:9), and whether the vertical refresh is one of the
+    /// CVT-standard rates (50, 60, 75, 85 Hz).
+    ///
+    /// A timing can fail these checks and still work fine on a given
+    /// monitor, but a non-standard timing is more likely to be rejected by
+    /// panels that only implement the spec-defined envelope.
+    pub fn check_standard(&self) -> StandardCheckReport {
+        // This compares the plain ratio of `h_active` to `v_active` (within
+        // 0.01) rather than re-deriving an `AspectRatio` via the
+        // cell-granularity-exact matching that `generate` uses internally.
+        // `generate` counts the left/right margins in `h_active` but not the
+        // top/bottom margins in `v_active`, so a timing generated with
+        // margins is judged on its margin-inclusive width.
+        const STANDARD_ASPECT_RATIOS: [f64; 5] =
+            [4.0 / 3.0, 16.0 / 9.0, 16.0 / 10.0, 5.0 / 4.0, 15.0 / 9.0];
+        let aspect_ratio = self.h_active as f64 / self.v_active as f64;
+        let aspect_ratio_is_standard = STANDARD_ASPECT_RATIOS
+            .iter()
+            .any(|standard| (aspect_ratio - standard).abs() < 0.01);
+
+        const STANDARD_REFRESH_RATES_HZ: [f64; 4] = [50.0, 60.0, 75.0, 85.0];
+        let refresh_rate_is_standard = STANDARD_REFRESH_RATES_HZ
+            .iter()
+            .any(|rate| (self.v_freq - rate).abs() < 0.01);
+
+        StandardCheckReport {
+            aspect_ratio_is_standard,
+            refresh_rate_is_standard,
+        }
+    }
+}
+
+/// The result of [`CvtTimings::check_standard`]: which VESA CVT conformance
+/// checks a timing passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StandardCheckReport {
+    /// Whether the timing's aspect ratio is one of the spec-defined ratios.
+    pub aspect_ratio_is_standard: bool,
+    /// Whether the timing's vertical refresh is one of the CVT-standard rates.
+    pub refresh_rate_is_standard: bool,
+}
+
+impl StandardCheckReport {
+    /// Whether every conformance check passed.
+    pub fn is_standard(&self) -> bool {
+        self.aspect_ratio_is_standard && self.refresh_rate_is_standard
+    }
 }
 
 fn get_aspect_ratio(
@@ -391,4 +889,107 @@ fn get_aspect_ratio(
     } else {
         return AspectRatio::AspectUnknown;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_a_generated_modeline() {
+        let generated = CvtTimings::generate(1920, 1080, 60.0, BlankingMode::Reduced, false, false);
+        let modeline = generated.generate_modeline();
+
+        let parsed = CvtTimings::parse_modeline(&modeline).unwrap();
+
+        // Everything derived straight from the modeline's integer fields
+        // round-trips exactly...
+        assert_eq!(parsed.pixel_clock, generated.pixel_clock);
+        assert_eq!(parsed.h_total, generated.h_total);
+        assert_eq!(parsed.h_active, generated.h_active);
+        assert_eq!(parsed.h_blank, generated.h_blank);
+        assert_eq!(parsed.h_front_porch, generated.h_front_porch);
+        assert_eq!(parsed.h_sync, generated.h_sync);
+        assert_eq!(parsed.h_back_porch, generated.h_back_porch);
+        assert_eq!(parsed.h_sync_polarity, generated.h_sync_polarity);
+        assert_eq!(parsed.v_total, generated.v_total);
+        assert_eq!(parsed.v_active, generated.v_active);
+        assert_eq!(parsed.v_blank, generated.v_blank);
+        assert_eq!(parsed.v_front_porch, generated.v_front_porch);
+        assert_eq!(parsed.v_sync, generated.v_sync);
+        assert_eq!(parsed.v_back_porch, generated.v_back_porch);
+        assert_eq!(parsed.v_sync_polarity, generated.v_sync_polarity);
+        assert_eq!(parsed.interlaced, generated.interlaced);
+
+        // ...but `generate`'s own h_freq/v_freq are rounded from
+        // higher-precision intermediates that aren't preserved in the
+        // modeline's integer fields, so only expect these to agree closely.
+        assert!((parsed.h_freq - generated.h_freq).abs() < 0.1);
+        assert!((parsed.v_freq - generated.v_freq).abs() < 0.1);
+    }
+
+    #[test]
+    fn parses_sync_polarity_and_interlace_flags() {
+        let parsed = CvtTimings::parse_modeline(
+            "Modeline \"1920x1080_60.00\" 173.00 1920 2048 2248 2576 1080 1083 1088 1120 -HSync +VSync Interlace",
+        )
+        .unwrap();
+
+        assert!(!parsed.h_sync_polarity);
+        assert!(parsed.v_sync_polarity);
+        assert!(parsed.interlaced);
+        assert_eq!(parsed.h_front_porch, 2048 - 1920);
+        assert_eq!(parsed.h_sync, 2248 - 2048);
+        assert_eq!(parsed.h_back_porch, 2576 - 2248);
+    }
+
+    #[test]
+    fn rejects_a_missing_keyword() {
+        let err = CvtTimings::parse_modeline("NotAModeline \"x\" 1 2 3 4 5 6 7 8 9").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::UnexpectedKeyword("NotAModeline".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_too_few_fields() {
+        let err = CvtTimings::parse_modeline("Modeline \"x\" 173.00 1920 2048 2248").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::TooFewFields {
+                expected: 9,
+                found: 4
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_an_invalid_number() {
+        let err = CvtTimings::parse_modeline(
+            "Modeline \"x\" notaclock 1920 2048 2248 2576 1080 1083 1088 1120",
+        )
+        .unwrap_err();
+        assert_eq!(err, ParseError::InvalidNumber("notaclock".to_string()));
+    }
+
+    #[test]
+    fn rejects_an_unknown_flag() {
+        let err = CvtTimings::parse_modeline(
+            "Modeline \"x\" 173.00 1920 2048 2248 2576 1080 1083 1088 1120 +Sideways",
+        )
+        .unwrap_err();
+        assert_eq!(err, ParseError::UnknownFlag("+sideways".to_string()));
+    }
+
+    #[test]
+    fn rejects_out_of_order_fields_instead_of_panicking() {
+        // hsyncstart (100) is smaller than hactive (1920): porch subtraction
+        // would underflow a plain `u32 -` instead of erroring.
+        let err = CvtTimings::parse_modeline(
+            "Modeline \"x\" 173.00 1920 100 2248 2576 1080 1083 1088 1120",
+        )
+        .unwrap_err();
+        assert_eq!(err, ParseError::InvalidOrdering);
+    }
+}
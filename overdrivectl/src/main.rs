@@ -1,9 +1,11 @@
+use std::ffi::CString;
 use std::ptr::null;
 
-use x11::{xrandr, xlib::{XOpenDisplay, XDefaultScreen, self}};
+use x11::{xlib, xrandr};
 
 fn main() {
-    println!("Hello, world!");
+    let output_name = std::env::args().nth(1);
+
     let a = cvt_utils::CvtTimings::generate(
         1280,
         1024,
@@ -12,19 +14,232 @@ fn main() {
         false,
         false,
     );
-    let a = a.generate_modeline();
-    println!("{}", &a);
+    let modeline = a.generate_modeline();
+    println!("{}", &modeline);
+
+    unsafe {
+        let dpy = xlib::XOpenDisplay(null());
+        if dpy.is_null() {
+            panic!("aaa");
+        }
+        let screen = xlib::XDefaultScreen(dpy);
+        let root = xlib::XRootWindow(dpy, screen);
+        let res = xrandr::XRRGetScreenResourcesCurrent(dpy, root);
+
+        match output_name {
+            Some(name) => {
+                match find_output_by_name(dpy, res, &name)
+                    .and_then(|output| read_edid_property(dpy, output))
+                {
+                    Some(raw_edid) => match cvt_utils::edid::Edid::parse(&raw_edid) {
+                        Ok(edid) => {
+                            if let Err(e) = a.validate_against(&edid) {
+                                eprintln!("warning: generated mode exceeds EDID limits: {}", e);
+                            }
+                        }
+                        Err(e) => eprintln!("warning: couldn't parse EDID for {}: {}", name, e),
+                    },
+                    None => eprintln!("warning: couldn't read EDID for {}", name),
+                }
+
+                match apply_mode(dpy, res, &name, &a) {
+                    Ok(mode) => println!("applied mode {} to output {}", mode, name),
+                    Err(e) => eprintln!("failed to apply mode to {}: {}", name, e),
+                }
+            }
+            None => {
+                dbg!((*res).modes);
+            }
+        }
+
+        xrandr::XRRFreeScreenResources(res);
+    }
+}
+
+/// Constructs an `XRRModeInfo` from a generated [`cvt_utils::CvtTimings`],
+/// registers it on the X server, attaches it to `output_name`, and (if that
+/// output already has a CRTC driving it) switches the CRTC over to the new
+/// mode right away.
+///
+/// Returns the `RRMode` id on success (the newly created mode, or the existing
+/// one reused when a mode with the same name is already registered), so
+/// callers can remove it again later with `XRRDeleteOutputMode`/`XRRDestroyMode`
+/// if needed.
+unsafe fn apply_mode(
+    dpy: *mut xlib::Display,
+    res: *mut xrandr::XRRScreenResources,
+    output_name: &str,
+    timings: &cvt_utils::CvtTimings,
+) -> Result<xrandr::RRMode, String> {
+    let output = find_output_by_name(dpy, res, output_name)
+        .ok_or_else(|| format!("no such output: {}", output_name))?;
+
+    let mut mode_flags: ::libc::c_ulong = 0;
+    mode_flags |= if timings.h_sync_polarity {
+        xrandr::RR_HSyncPositive
+    } else {
+        xrandr::RR_HSyncNegative
+    } as ::libc::c_ulong;
+    mode_flags |= if timings.v_sync_polarity {
+        xrandr::RR_VSyncPositive
+    } else {
+        xrandr::RR_VSyncNegative
+    } as ::libc::c_ulong;
+    if timings.interlaced {
+        mode_flags |= xrandr::RR_Interlace as ::libc::c_ulong;
+    }
+
+    let name = format!(
+        "{}x{}_{:.2}{}",
+        timings.h_active,
+        timings.v_active,
+        timings.v_freq,
+        if timings.interlaced { "i" } else { "" }
+    );
+
+    // The X server rejects `XRRCreateMode` with a `BadName` protocol error
+    // (which, with no custom error handler installed, aborts the whole
+    // process) if a mode with this exact name already exists. Re-tuning the
+    // same geometry/refresh is the normal workflow for this tool, so reuse
+    // the existing mode instead of trying to create a duplicate.
+    let mode = match find_mode_by_name(res, &name) {
+        Some(existing) => existing,
+        None => {
+            let name = CString::new(name).map_err(|e| e.to_string())?;
+
+            let mut mode_info = xrandr::XRRModeInfo {
+                id: 0,
+                width: timings.h_active,
+                height: timings.v_active,
+                dotClock: timings.pixel_clock as ::libc::c_ulong,
+                hSyncStart: timings.h_active + timings.h_front_porch,
+                hSyncEnd: timings.h_active + timings.h_front_porch + timings.h_sync,
+                hTotal: timings.h_total,
+                hSkew: 0,
+                vSyncStart: timings.v_active + timings.v_front_porch,
+                vSyncEnd: timings.v_active + timings.v_front_porch + timings.v_sync,
+                vTotal: timings.v_total,
+                name: name.as_ptr() as *mut _,
+                nameLength: name.as_bytes().len() as ::libc::c_uint,
+                modeFlags: mode_flags,
+            };
+
+            let window = xlib::XDefaultRootWindow(dpy);
+            let mode = xrandr::XRRCreateMode(dpy, window, &mut mode_info);
+            if mode == 0 {
+                return Err("XRRCreateMode failed".to_string());
+            }
+            mode
+        }
+    };
+
+    xrandr::XRRAddOutputMode(dpy, output, mode);
+
+    let output_info = xrandr::XRRGetOutputInfo(dpy, res, output);
+    if !output_info.is_null() && (*output_info).crtc != 0 {
+        let crtc = (*output_info).crtc;
+        let mut outputs = [output];
+        xrandr::XRRSetCrtcConfig(
+            dpy,
+            res,
+            crtc,
+            (*res).configTimestamp,
+            0,
+            0,
+            mode,
+            xrandr::RR_Rotate_0 as u16,
+            outputs.as_mut_ptr(),
+            1,
+        );
+    }
+    if !output_info.is_null() {
+        xrandr::XRRFreeOutputInfo(output_info);
+    }
 
-unsafe {
+    Ok(mode)
+}
 
-    let dpy = xlib::XOpenDisplay(null());
-    if dpy.is_null() {
-        panic!("aaa");
+/// Looks up an already-registered `RRMode` by its exact name, if the screen
+/// resources already know about one (the `XRRModeInfo::name` field is not
+/// NUL-terminated, so the comparison is done over the raw name bytes).
+unsafe fn find_mode_by_name(
+    res: *mut xrandr::XRRScreenResources,
+    name: &str,
+) -> Option<xrandr::RRMode> {
+    for i in 0..(*res).nmode {
+        let mode_info = *(*res).modes.offset(i as isize);
+        let mode_name =
+            std::slice::from_raw_parts(mode_info.name as *const u8, mode_info.nameLength as usize);
+        if mode_name == name.as_bytes() {
+            return Some(mode_info.id);
+        }
     }
-    let screen = xlib::XDefaultScreen(dpy);
-    let root = xlib::XRootWindow(dpy, screen);
-    let res = xrandr::XRRGetScreenResourcesCurrent(dpy, root);
 
-    dbg!((*res).modes);
+    None
 }
+
+/// Reads the raw `EDID` output property XRandR exposes for a connected
+/// output, if the server has one to hand back.
+unsafe fn read_edid_property(dpy: *mut xlib::Display, output: xrandr::RROutput) -> Option<Vec<u8>> {
+    let atom_name = CString::new("EDID").ok()?;
+    let edid_atom = xlib::XInternAtom(dpy, atom_name.as_ptr(), xlib::True);
+    if edid_atom == 0 {
+        return None;
+    }
+
+    let mut actual_type: xlib::Atom = 0;
+    let mut actual_format: ::libc::c_int = 0;
+    let mut nitems: ::libc::c_ulong = 0;
+    let mut bytes_after: ::libc::c_ulong = 0;
+    let mut prop: *mut ::libc::c_uchar = std::ptr::null_mut();
+
+    let status = xrandr::XRRGetOutputProperty(
+        dpy,
+        output,
+        edid_atom,
+        0,
+        128,
+        xlib::False,
+        xlib::False,
+        xlib::AnyPropertyType as ::libc::c_ulong,
+        &mut actual_type,
+        &mut actual_format,
+        &mut nitems,
+        &mut bytes_after,
+        &mut prop,
+    );
+
+    if status != xlib::Success as ::libc::c_int || prop.is_null() || nitems == 0 {
+        return None;
+    }
+
+    let data = std::slice::from_raw_parts(prop, nitems as usize).to_vec();
+    xlib::XFree(prop as *mut _);
+    Some(data)
+}
+
+/// Looks up an `RROutput` by its XRandR-reported name (e.g. `"DP-1"`).
+unsafe fn find_output_by_name(
+    dpy: *mut xlib::Display,
+    res: *mut xrandr::XRRScreenResources,
+    output_name: &str,
+) -> Option<xrandr::RROutput> {
+    for i in 0..(*res).noutput {
+        let output = *(*res).outputs.offset(i as isize);
+        let info = xrandr::XRRGetOutputInfo(dpy, res, output);
+        if info.is_null() {
+            continue;
+        }
+
+        let name = std::ffi::CStr::from_ptr((*info).name)
+            .to_string_lossy()
+            .into_owned();
+        xrandr::XRRFreeOutputInfo(info);
+
+        if name == output_name {
+            return Some(output);
+        }
+    }
+
+    None
 }